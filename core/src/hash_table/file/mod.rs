@@ -4,75 +4,38 @@ use holochain_core_types::{
     entry_meta::EntryMeta,
     error::HolochainError,
 };
-use std::{
-    fs::{self, create_dir_all},
-    path::{Path, MAIN_SEPARATOR},
-};
-
-use hash_table::HashTable;
-use walkdir::WalkDir;
 
-// folders actually... wish-it-was-tables
-#[derive(Debug, Clone)]
-enum Table {
-    Entries,
-    Metas,
-}
+use hash_table::{
+    store::{FilesystemStore, ObjectStore, Table},
+    HashTable,
+};
 
-impl ToString for Table {
-    fn to_string(&self) -> String {
-        match self {
-            Table::Entries => "entries",
-            Table::Metas => "metas",
-        }.to_string()
-    }
+/// A HashTable layered over any ObjectStore. Entries and metas are just
+/// AddressableContent stored under their address in the relevant logical
+/// table, so the table logic is independent of where the bytes live.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreTable<S: ObjectStore> {
+    store: S,
 }
 
-#[derive(Serialize, Debug, PartialEq, Clone)]
-pub struct FileTable {
-    path: String,
-}
+/// the filesystem-backed table, preserving the historical `FileTable` name
+pub type FileTable = StoreTable<FilesystemStore>;
 
 impl FileTable {
-    /// attempts to build a new FileTable
+    /// attempts to build a new filesystem-backed table at the given path
     /// can fail if the given path can't be resolved to a directory on the filesystem
     /// can fail if permissions don't allow access to the directory on the filesystem
     pub fn new(path: &str) -> Result<FileTable, HolochainError> {
-        let canonical = Path::new(path).canonicalize()?;
-        if canonical.is_dir() {
-            Ok(FileTable {
-                path: match canonical.to_str() {
-                    Some(p) => p.to_string(),
-                    None => {
-                        return Err(HolochainError::IoError(
-                            "could not convert path to string".to_string(),
-                        ));
-                    }
-                },
-            })
-        } else {
-            Err(HolochainError::IoError(
-                "path is not a directory or permissions don't allow access".to_string(),
-            ))
-        }
+        Ok(StoreTable {
+            store: FilesystemStore::new(path)?,
+        })
     }
+}
 
-    /// given a Table enum, ensure that the correct sub-directory exists and return the string path
-    fn dir(&self, table: Table) -> Result<String, HolochainError> {
-        let dir_string = format!("{}{}{}", self.path, MAIN_SEPARATOR, table.to_string());
-        // @TODO be more efficient here
-        // @see https://github.com/holochain/holochain-rust/issues/248
-        create_dir_all(&dir_string)?;
-        Ok(dir_string)
-    }
-
-    fn addressable_content_path(
-        &self,
-        table: Table,
-        address: &Address,
-    ) -> Result<String, HolochainError> {
-        let dir = self.dir(table)?;
-        Ok(format!("{}{}{}.json", dir, MAIN_SEPARATOR, address))
+impl<S: ObjectStore> StoreTable<S> {
+    /// builds a table over an already-constructed store
+    pub fn with_store(store: S) -> StoreTable<S> {
+        StoreTable { store }
     }
 
     fn upsert<AC: AddressableContent>(
@@ -80,33 +43,21 @@ impl FileTable {
         table: Table,
         addressable_content: &AC,
     ) -> Result<(), HolochainError> {
-        match fs::write(
-            self.addressable_content_path(table, &addressable_content.address())?,
-            addressable_content.content(),
-        ) {
-            Err(e) => Err(HolochainError::from(e)),
-            _ => Ok(()),
-        }
-    }
-
-    /// Returns a JSON string option for the given key in the given table
-    fn lookup(&self, table: Table, address: &Address) -> Result<Option<String>, HolochainError> {
-        let path_string = self.addressable_content_path(table, address)?;
-        if Path::new(&path_string).is_file() {
-            Ok(Some(fs::read_to_string(path_string)?))
-        } else {
-            Ok(None)
-        }
+        self.store.put(
+            table,
+            &addressable_content.address(),
+            &addressable_content.content(),
+        )
     }
 }
 
-impl HashTable for FileTable {
+impl<S: ObjectStore> HashTable for StoreTable<S> {
     fn put_entry(&mut self, entry: &Entry) -> Result<(), HolochainError> {
         self.upsert(Table::Entries, entry)
     }
 
     fn entry(&self, address: &Address) -> Result<Option<Entry>, HolochainError> {
-        match self.lookup(Table::Entries, address)? {
+        match self.store.get(Table::Entries, address)? {
             Some(content) => Ok(Some(Entry::from_content(&content))),
             None => Ok(None),
         }
@@ -117,45 +68,52 @@ impl HashTable for FileTable {
     }
 
     fn get_meta(&mut self, address: &Address) -> Result<Option<EntryMeta>, HolochainError> {
-        match self.lookup(Table::Metas, address)? {
+        match self.store.get(Table::Metas, address)? {
             Some(content) => Ok(Some(EntryMeta::from_content(&content))),
             None => Ok(None),
         }
     }
 
     fn metas_from_entry(&mut self, entry: &Entry) -> Result<Vec<EntryMeta>, HolochainError> {
-        let mut metas = Vec::new();
-
-        // this is a brute force approach that involves reading and parsing every file
-        // big meta data should be backed by something indexed like sqlite
-        for meta in WalkDir::new(self.dir(Table::Metas)?) {
-            let meta = meta?;
-            let path = meta.path();
-            if let Some(stem) = path.file_stem() {
-                if let Some(address_string) = stem.to_str() {
-                    if let Some(meta) = self.get_meta(&Address::from(address_string.to_string()))? {
-                        if meta.entry_address() == &entry.address() {
-                            metas.push(meta);
-                        }
-                    }
-                }
-            }
-        }
+        // a single store.list instead of walking the filesystem directly
+        let mut metas: Vec<EntryMeta> = self
+            .store
+            .list(Table::Metas)?
+            .iter()
+            .map(|content| EntryMeta::from_content(content))
+            .filter(|meta| meta.entry_address() == &entry.address())
+            .collect();
 
         // @TODO should this be sorted at all at this point?
         // @see https://github.com/holochain/holochain-rust/issues/144
         metas.sort();
         Ok(metas)
     }
+
+    fn all_metas(&mut self) -> Result<Vec<EntryMeta>, HolochainError> {
+        // no index, so list every stored meta; query_metas scans and filters
+        Ok(self
+            .store
+            .list(Table::Metas)?
+            .iter()
+            .map(|content| EntryMeta::from_content(content))
+            .collect())
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
-    use super::Table;
-    use hash_table::{file::FileTable, test_util::standard_suite};
-    use holochain_core_types::cas::content::{Address, AddressableContent, Content};
-    use regex::Regex;
-    use std::path::MAIN_SEPARATOR;
+    use hash_table::{
+        file::{FileTable, StoreTable},
+        query::MetaQuery,
+        store::InMemoryStore,
+        test_util::standard_suite,
+        HashTable,
+    };
+    use holochain_core_types::{
+        cas::content::Address,
+        entry_meta::{test_attribute_a, test_value_a, EntryMeta},
+    };
     use tempfile::{tempdir, TempDir};
 
     /// returns a new FileTable for testing and the TempDir created for it
@@ -172,12 +130,6 @@ pub mod tests {
         let (_table, _dir) = test_table();
     }
 
-    #[test]
-    fn test_standard_suite() {
-        let (mut table, _dir) = test_table();
-        standard_suite(&mut table);
-    }
-
     #[test]
     /// a missing directory gives an error result
     fn new_error_missing_dir() {
@@ -186,99 +138,43 @@ pub mod tests {
     }
 
     #[test]
-    /// dir returns a sensible string for every Table enum variant
-    fn test_dir() {
-        let (table, _dir) = test_table();
-        let re = |s| {
-            let regex_str = if MAIN_SEPARATOR == '\\' {
-                format!(r".*\.tmp.*\{}{}", MAIN_SEPARATOR, s)
-            } else {
-                format!(r".*\.tmp.*{}{}", MAIN_SEPARATOR, s)
-            };
-            Regex::new(&regex_str).expect("failed to build regex")
-        };
-
-        for (s, t) in vec![("entries", Table::Entries), ("metas", Table::Metas)] {
-            assert!(
-                re(s).is_match(
-                    &table
-                        .dir(t.clone())
-                        .expect(&format!("could not get dir for {:?}", t)),
-                )
-            );
-        }
+    /// the filesystem-backed table passes the standard suite
+    fn test_standard_suite_filesystem() {
+        let (mut table, _dir) = test_table();
+        standard_suite(&mut table);
     }
 
     #[test]
-    /// row_path returns a sensible string for a Table enum and key
-    fn test_row_path() {
-        let (table, _dir) = test_table();
-
-        let re = |s, k| {
-            let regex_str = if MAIN_SEPARATOR == '\\' {
-                format!(
-                    r".*\.tmp.*\{}{}\{}{}\.json",
-                    MAIN_SEPARATOR, s, MAIN_SEPARATOR, k
-                )
-            } else {
-                format!(
-                    r".*\.tmp.*{}{}{}{}\.json",
-                    MAIN_SEPARATOR, s, MAIN_SEPARATOR, k
-                )
-            };
-            Regex::new(&regex_str).expect("failed to build regex")
-        };
-
-        for (s, t) in vec![("entries", Table::Entries), ("metas", Table::Metas)] {
-            for k in vec!["foo", "bar"] {
-                assert!(
-                    re(s, k).is_match(
-                        &table
-                            .addressable_content_path(t.clone(), &Address::from(k.to_string()))
-                            .expect(&format!("could not get row path for {:?} in {:?}", k, t)),
-                    )
-                );
-            }
-        }
+    /// the in-memory-backed table passes the same standard suite
+    fn test_standard_suite_in_memory() {
+        let mut table = StoreTable::with_store(InMemoryStore::new());
+        standard_suite(&mut table);
     }
 
     #[test]
-    /// data can round trip through upsert/lookup
-    fn test_data_round_trip() {
-        #[derive(Serialize)]
-        struct SomeData {
-            data: String,
+    /// the scan-and-filter query path filters a seeded set by attribute and by source
+    fn test_query_metas() {
+        let mut table = StoreTable::with_store(InMemoryStore::new());
+        let entry_one = Address::from("1".to_string());
+        let entry_two = Address::from("2".to_string());
+        let a = &test_attribute_a();
+
+        let metas = vec![
+            EntryMeta::new("alice", &entry_one, a, &test_value_a()),
+            EntryMeta::new("alice", &entry_two, a, &test_value_a()),
+            EntryMeta::new("bob", &entry_two, "other", "v"),
+        ];
+        for meta in &metas {
+            table.assert_meta(meta).unwrap();
         }
 
-        impl AddressableContent for SomeData {
-            fn content(&self) -> Content {
-                self.data.clone()
-            }
-
-            fn from_content(content: &Content) -> Self {
-                SomeData {
-                    data: content.to_string(),
-                }
-            }
-        }
-
-        let data = SomeData {
-            data: "foo".to_string(),
-        };
-        let s = data.content();
-
-        let (table, _dir) = test_table();
-
-        table
-            .upsert(Table::Entries, &data)
-            .expect("could not upsert data");
-
         assert_eq!(
-            Some(s),
-            table
-                .lookup(Table::Entries, &data.address())
-                .expect("could not lookup data"),
+            vec![metas[0].clone(), metas[1].clone()],
+            table.query_metas(&MetaQuery::new().with_attribute(a)).unwrap(),
+        );
+        assert_eq!(
+            vec![metas[2].clone()],
+            table.query_metas(&MetaQuery::new().with_source("bob")).unwrap(),
         );
     }
-
 }
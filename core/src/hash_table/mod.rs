@@ -0,0 +1,59 @@
+use holochain_core_types::{
+    cas::content::Address, entry::Entry, entry_meta::EntryMeta, error::HolochainError,
+};
+
+use hash_table::query::MetaQuery;
+
+pub mod file;
+pub mod query;
+pub mod sqlite;
+pub mod store;
+#[cfg(test)]
+pub mod test_util;
+
+/// A content-addressed store of entries and the meta assertions made against
+/// them. Backends differ only in where the bytes live and whether meta lookups
+/// are indexed; the retrieval semantics on top are shared here.
+pub trait HashTable {
+    fn put_entry(&mut self, entry: &Entry) -> Result<(), HolochainError>;
+
+    fn entry(&self, address: &Address) -> Result<Option<Entry>, HolochainError>;
+
+    fn assert_meta(&mut self, meta: &EntryMeta) -> Result<(), HolochainError>;
+
+    fn get_meta(&mut self, address: &Address) -> Result<Option<EntryMeta>, HolochainError>;
+
+    fn metas_from_entry(&mut self, entry: &Entry) -> Result<Vec<EntryMeta>, HolochainError>;
+
+    /// every meta assertion held by the table, used by the default query path
+    fn all_metas(&mut self) -> Result<Vec<EntryMeta>, HolochainError>;
+
+    /// query metas across all entries by attribute/value/source. the default is
+    /// a scan-and-filter over `all_metas`; indexed backends override this with
+    /// a pushed-down lookup
+    fn query_metas(&mut self, query: &MetaQuery) -> Result<Vec<EntryMeta>, HolochainError> {
+        let mut metas: Vec<EntryMeta> = self
+            .all_metas()?
+            .into_iter()
+            .filter(|meta| query.matches(meta))
+            .collect();
+        metas.sort();
+        Ok(metas)
+    }
+
+    /// the winning assertion for an (entry, attribute) pair.
+    /// metas_from_entry returns them sorted, and EntryMeta's Ord ranks a tie of
+    /// (entry, attribute) by (txn, source), so the winner is simply the
+    /// greatest matching meta — deterministic last-writer-wins
+    fn latest_meta_for(
+        &mut self,
+        entry: &Entry,
+        attribute: &str,
+    ) -> Result<Option<EntryMeta>, HolochainError> {
+        Ok(self
+            .metas_from_entry(entry)?
+            .into_iter()
+            .filter(|meta| meta.attribute() == attribute)
+            .max())
+    }
+}
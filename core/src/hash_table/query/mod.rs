@@ -0,0 +1,48 @@
+use holochain_core_types::entry_meta::EntryMeta;
+
+/// A typed query over meta assertions. Each populated field is a predicate that
+/// a meta must satisfy to match; an unset (`None`) field matches anything. This
+/// keeps the query expression (what to match) separate from execution (how to
+/// match), so a scanning backend and an indexed backend can share the same
+/// query type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetaQuery {
+    pub attribute: Option<String>,
+    pub value: Option<String>,
+    pub source: Option<String>,
+}
+
+impl MetaQuery {
+    /// an empty query matching every meta
+    pub fn new() -> MetaQuery {
+        MetaQuery::default()
+    }
+
+    /// constrain to a given attribute name
+    pub fn with_attribute(mut self, attribute: &str) -> MetaQuery {
+        self.attribute = Some(attribute.to_string());
+        self
+    }
+
+    /// constrain to a given attribute value
+    pub fn with_value(mut self, value: &str) -> MetaQuery {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    /// constrain to a given asserting source
+    pub fn with_source(mut self, source: &str) -> MetaQuery {
+        self.source = Some(source.to_string());
+        self
+    }
+
+    /// whether a meta satisfies every populated predicate — the scan-and-filter
+    /// execution path used by stores without an index
+    pub fn matches(&self, meta: &EntryMeta) -> bool {
+        self.attribute
+            .as_ref()
+            .map_or(true, |a| &meta.attribute() == a)
+            && self.value.as_ref().map_or(true, |v| &meta.value() == v)
+            && self.source.as_ref().map_or(true, |s| &meta.source() == s)
+    }
+}
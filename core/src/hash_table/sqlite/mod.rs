@@ -0,0 +1,298 @@
+use holochain_core_types::{
+    cas::content::{Address, AddressableContent},
+    entry::Entry,
+    entry_meta::EntryMeta,
+    error::HolochainError,
+};
+
+use hash_table::{query::MetaQuery, HashTable};
+use rusqlite::{types::ToSql, Connection, Error as SqliteError};
+
+/// a HashTable backed by a relational (SQLite) store
+///
+/// entries live in a single `entries` table keyed by address, while metas are
+/// stored as EAV rows in `metas` with an index on `entry_address` (and on
+/// `attribute`) so `metas_from_entry` is a single indexed `SELECT` rather than
+/// the brute force directory scan that `FileTable` performs
+/// @see https://github.com/holochain/holochain-rust/issues/248
+#[derive(Debug)]
+pub struct SqliteTable {
+    conn: Connection,
+}
+
+impl SqliteTable {
+    /// attempts to build a new SqliteTable at the given path
+    /// can fail if the path can't be opened as a SQLite database
+    pub fn new(path: &str) -> Result<SqliteTable, HolochainError> {
+        let conn = Connection::open(path)
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+        SqliteTable::from_connection(conn)
+    }
+
+    /// builds a new in-memory SqliteTable, handy for tests that don't want a tempdir
+    pub fn new_in_memory() -> Result<SqliteTable, HolochainError> {
+        let conn = Connection::open_in_memory()
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+        SqliteTable::from_connection(conn)
+    }
+
+    /// ensures the schema exists on the given connection and wraps it
+    fn from_connection(conn: Connection) -> Result<SqliteTable, HolochainError> {
+        conn.execute_batch(
+            "
+            CREATE TABLE IF NOT EXISTS entries (
+                address TEXT PRIMARY KEY NOT NULL,
+                content TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS metas (
+                address TEXT PRIMARY KEY NOT NULL,
+                entry_address TEXT NOT NULL,
+                attribute TEXT NOT NULL,
+                value TEXT NOT NULL,
+                source TEXT NOT NULL,
+                content TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS metas_entry_address ON metas (entry_address);
+            CREATE INDEX IF NOT EXISTS metas_attribute ON metas (attribute);
+            ",
+        ).map_err(|e| HolochainError::IoError(e.to_string()))?;
+        Ok(SqliteTable { conn })
+    }
+}
+
+/// fetch a single `content` column by address, returning `None` only for a
+/// genuinely absent row and surfacing every other rusqlite error as an
+/// IoError — so a corrupt or broken store never masquerades as empty
+fn lookup_content(
+    conn: &Connection,
+    sql: &str,
+    address: &Address,
+) -> Result<Option<String>, HolochainError> {
+    match conn.query_row(sql, &[&address.to_string()], |row| row.get(0)) {
+        Ok(content) => Ok(Some(content)),
+        Err(SqliteError::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(HolochainError::IoError(e.to_string())),
+    }
+}
+
+impl HashTable for SqliteTable {
+    fn put_entry(&mut self, entry: &Entry) -> Result<(), HolochainError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO entries (address, content) VALUES (?1, ?2)",
+                &[&entry.address().to_string() as &ToSql, &entry.content()],
+            )
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn entry(&self, address: &Address) -> Result<Option<Entry>, HolochainError> {
+        let content = lookup_content(
+            &self.conn,
+            "SELECT content FROM entries WHERE address = ?1",
+            address,
+        )?;
+        Ok(content.map(|c| Entry::from_content(&c)))
+    }
+
+    fn assert_meta(&mut self, meta: &EntryMeta) -> Result<(), HolochainError> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO metas
+                    (address, entry_address, attribute, value, source, content)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                &[
+                    &meta.address().to_string() as &ToSql,
+                    &meta.entry_address().to_string(),
+                    &meta.attribute(),
+                    &meta.value(),
+                    &meta.source(),
+                    &meta.content(),
+                ],
+            )
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn get_meta(&mut self, address: &Address) -> Result<Option<EntryMeta>, HolochainError> {
+        let content = lookup_content(
+            &self.conn,
+            "SELECT content FROM metas WHERE address = ?1",
+            address,
+        )?;
+        Ok(content.map(|c| EntryMeta::from_content(&c)))
+    }
+
+    fn metas_from_entry(&mut self, entry: &Entry) -> Result<Vec<EntryMeta>, HolochainError> {
+        // a single indexed lookup rather than walking every meta on disk
+        let mut stmt = self
+            .conn
+            .prepare("SELECT content FROM metas WHERE entry_address = ?1")
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+
+        let entry_address = entry.address().to_string();
+        let rows = stmt
+            .query_map(&[&entry_address], |row| {
+                let content: String = row.get(0);
+                EntryMeta::from_content(&content)
+            })
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+
+        let mut metas = Vec::new();
+        for meta in rows {
+            metas.push(meta.map_err(|e| HolochainError::IoError(e.to_string()))?);
+        }
+
+        metas.sort();
+        Ok(metas)
+    }
+
+    fn all_metas(&mut self) -> Result<Vec<EntryMeta>, HolochainError> {
+        self.query_metas(&MetaQuery::new())
+    }
+
+    /// indexed override of the trait's scan default: each populated predicate
+    /// becomes a `WHERE` clause, so filtering by attribute (indexed) or
+    /// value/source is pushed down to SQLite rather than scanned in Rust
+    fn query_metas(&mut self, query: &MetaQuery) -> Result<Vec<EntryMeta>, HolochainError> {
+        let mut clauses: Vec<&str> = Vec::new();
+        let mut params: Vec<&ToSql> = Vec::new();
+        if let Some(ref attribute) = query.attribute {
+            clauses.push("attribute = ?");
+            params.push(attribute);
+        }
+        if let Some(ref value) = query.value {
+            clauses.push("value = ?");
+            params.push(value);
+        }
+        if let Some(ref source) = query.source {
+            clauses.push("source = ?");
+            params.push(source);
+        }
+
+        let sql = if clauses.is_empty() {
+            "SELECT content FROM metas".to_string()
+        } else {
+            format!("SELECT content FROM metas WHERE {}", clauses.join(" AND "))
+        };
+
+        let mut stmt = self
+            .conn
+            .prepare(&sql)
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+        let rows = stmt
+            .query_map(&params, |row| {
+                let content: String = row.get(0);
+                EntryMeta::from_content(&content)
+            })
+            .map_err(|e| HolochainError::IoError(e.to_string()))?;
+
+        let mut metas = Vec::new();
+        for meta in rows {
+            metas.push(meta.map_err(|e| HolochainError::IoError(e.to_string()))?);
+        }
+        metas.sort();
+        Ok(metas)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use hash_table::{query::MetaQuery, sqlite::SqliteTable, test_util::standard_suite, HashTable};
+    use holochain_core_types::{
+        cas::content::Address,
+        entry_meta::{test_attribute_a, test_attribute_b, test_value_a, test_value_b, EntryMeta},
+    };
+
+    /// returns a new in-memory SqliteTable for testing
+    pub fn test_table() -> SqliteTable {
+        SqliteTable::new_in_memory().unwrap()
+    }
+
+    #[test]
+    /// smoke test
+    fn new() {
+        let _table = test_table();
+    }
+
+    #[test]
+    fn test_standard_suite() {
+        let mut table = test_table();
+        standard_suite(&mut table);
+    }
+
+    #[test]
+    /// conflicting assertions for the same (entry, attribute) coexist in storage
+    /// and latest_meta_for arbitrates them by (txn, source) — last writer wins
+    fn test_conflicting_metas_coexist() {
+        use holochain_core_types::entry::test_entry;
+        let mut table = test_table();
+        let entry = test_entry();
+
+        let mut clock = ::holochain_core_types::logical_clock::LogicalClock::new();
+        let older = EntryMeta::new_stamped(
+            &mut clock,
+            "alice",
+            &entry.address(),
+            &test_attribute_a(),
+            &test_value_a(),
+        );
+        let newer = EntryMeta::new_stamped(
+            &mut clock,
+            "alice",
+            &entry.address(),
+            &test_attribute_a(),
+            &test_value_b(),
+        );
+        table.assert_meta(&older).unwrap();
+        table.assert_meta(&newer).unwrap();
+
+        // both survive the write rather than one clobbering the other
+        assert_eq!(2, table.metas_from_entry(&entry).unwrap().len());
+        assert_eq!(
+            Some(newer),
+            table.latest_meta_for(&entry, &test_attribute_a()).unwrap(),
+        );
+    }
+
+    #[test]
+    /// query_metas filters a seeded set by attribute and value across entries
+    fn test_query_metas() {
+        let mut table = test_table();
+        let entry_one = Address::from("1".to_string());
+        let entry_two = Address::from("2".to_string());
+        let a = &test_attribute_a();
+        let b = &test_attribute_b();
+
+        let metas = vec![
+            EntryMeta::new("alice", &entry_one, a, &test_value_a()),
+            EntryMeta::new("alice", &entry_two, a, &test_value_b()),
+            EntryMeta::new("bob", &entry_two, b, &test_value_a()),
+        ];
+        for meta in &metas {
+            table.assert_meta(meta).unwrap();
+        }
+
+        // by attribute across entries
+        assert_eq!(
+            vec![metas[0].clone(), metas[1].clone()],
+            table.query_metas(&MetaQuery::new().with_attribute(a)).unwrap(),
+        );
+
+        // reverse lookup: which metas have this value
+        assert_eq!(
+            vec![metas[0].clone(), metas[2].clone()],
+            table.query_metas(&MetaQuery::new().with_value(&test_value_a())).unwrap(),
+        );
+
+        // combined predicates, including source
+        assert_eq!(
+            vec![metas[2].clone()],
+            table
+                .query_metas(
+                    &MetaQuery::new().with_attribute(b).with_source("bob")
+                )
+                .unwrap(),
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use holochain_core_types::{cas::content::Address, error::HolochainError};
+use std::{
+    collections::HashMap,
+    fs::{self, create_dir_all},
+    path::{Path, MAIN_SEPARATOR},
+    sync::Mutex,
+};
+use walkdir::WalkDir;
+
+/// a logical "table" within a store — folders-actually... wish-it-was-tables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Table {
+    Entries,
+    Metas,
+}
+
+impl ToString for Table {
+    fn to_string(&self) -> String {
+        match self {
+            Table::Entries => "entries",
+            Table::Metas => "metas",
+        }.to_string()
+    }
+}
+
+/// A small object store: get/put/list content by key within a logical table.
+///
+/// This is the layer that decouples `HashTable` logic from where bytes
+/// actually live, so a filesystem backend and an in-memory backend (and later
+/// a remote/S3 backend) can be swapped without touching table logic.
+pub trait ObjectStore {
+    /// store `content` under `key` in `table`, overwriting any existing value
+    fn put(&self, table: Table, key: &Address, content: &str) -> Result<(), HolochainError>;
+
+    /// fetch the content stored under `key` in `table`, if any
+    fn get(&self, table: Table, key: &Address) -> Result<Option<String>, HolochainError>;
+
+    /// list every stored content value in `table`
+    fn list(&self, table: Table) -> Result<Vec<String>, HolochainError>;
+}
+
+/// An ObjectStore backed by the local filesystem, one directory per table and
+/// one `<address>.json` file per object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilesystemStore {
+    path: String,
+}
+
+impl FilesystemStore {
+    /// attempts to build a new FilesystemStore rooted at the given directory
+    /// can fail if the path can't be resolved to a directory on the filesystem
+    pub fn new(path: &str) -> Result<FilesystemStore, HolochainError> {
+        let canonical = Path::new(path).canonicalize()?;
+        if canonical.is_dir() {
+            Ok(FilesystemStore {
+                path: match canonical.to_str() {
+                    Some(p) => p.to_string(),
+                    None => {
+                        return Err(HolochainError::IoError(
+                            "could not convert path to string".to_string(),
+                        ));
+                    }
+                },
+            })
+        } else {
+            Err(HolochainError::IoError(
+                "path is not a directory or permissions don't allow access".to_string(),
+            ))
+        }
+    }
+
+    /// given a Table, ensure the sub-directory exists and return its path
+    pub fn dir(&self, table: Table) -> Result<String, HolochainError> {
+        let dir_string = format!("{}{}{}", self.path, MAIN_SEPARATOR, table.to_string());
+        // @TODO be more efficient here
+        // @see https://github.com/holochain/holochain-rust/issues/248
+        create_dir_all(&dir_string)?;
+        Ok(dir_string)
+    }
+
+    pub fn object_path(&self, table: Table, key: &Address) -> Result<String, HolochainError> {
+        let dir = self.dir(table)?;
+        Ok(format!("{}{}{}.json", dir, MAIN_SEPARATOR, key))
+    }
+}
+
+impl ObjectStore for FilesystemStore {
+    fn put(&self, table: Table, key: &Address, content: &str) -> Result<(), HolochainError> {
+        fs::write(self.object_path(table, key)?, content)?;
+        Ok(())
+    }
+
+    fn get(&self, table: Table, key: &Address) -> Result<Option<String>, HolochainError> {
+        let path_string = self.object_path(table, key)?;
+        if Path::new(&path_string).is_file() {
+            Ok(Some(fs::read_to_string(path_string)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn list(&self, table: Table) -> Result<Vec<String>, HolochainError> {
+        let mut contents = Vec::new();
+        for entry in WalkDir::new(self.dir(table)?) {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_file() {
+                contents.push(fs::read_to_string(path)?);
+            }
+        }
+        Ok(contents)
+    }
+}
+
+/// An ObjectStore that keeps everything in memory. Handy for fast tests that
+/// don't want to touch the filesystem or manage a tempdir.
+#[derive(Debug, Default)]
+pub struct InMemoryStore {
+    tables: Mutex<HashMap<String, HashMap<String, String>>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> InMemoryStore {
+        InMemoryStore {
+            tables: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl ObjectStore for InMemoryStore {
+    fn put(&self, table: Table, key: &Address, content: &str) -> Result<(), HolochainError> {
+        let mut tables = self.tables.lock().unwrap();
+        tables
+            .entry(table.to_string())
+            .or_insert_with(HashMap::new)
+            .insert(key.to_string(), content.to_string());
+        Ok(())
+    }
+
+    fn get(&self, table: Table, key: &Address) -> Result<Option<String>, HolochainError> {
+        let tables = self.tables.lock().unwrap();
+        Ok(tables
+            .get(&table.to_string())
+            .and_then(|t| t.get(&key.to_string()).cloned()))
+    }
+
+    fn list(&self, table: Table) -> Result<Vec<String>, HolochainError> {
+        let tables = self.tables.lock().unwrap();
+        Ok(match tables.get(&table.to_string()) {
+            Some(t) => t.values().cloned().collect(),
+            None => Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use holochain_core_types::cas::content::Address;
+    use regex::Regex;
+    use tempfile::{tempdir, TempDir};
+
+    fn test_fs_store() -> (FilesystemStore, TempDir) {
+        let dir = tempdir().unwrap();
+        (
+            FilesystemStore::new(dir.path().to_str().unwrap()).unwrap(),
+            dir,
+        )
+    }
+
+    #[test]
+    /// dir returns a sensible string for every Table variant
+    fn test_dir() {
+        let (store, _dir) = test_fs_store();
+        let re = |s| {
+            let regex_str = if MAIN_SEPARATOR == '\\' {
+                format!(r".*\.tmp.*\{}{}", MAIN_SEPARATOR, s)
+            } else {
+                format!(r".*\.tmp.*{}{}", MAIN_SEPARATOR, s)
+            };
+            Regex::new(&regex_str).expect("failed to build regex")
+        };
+        for (s, t) in vec![("entries", Table::Entries), ("metas", Table::Metas)] {
+            assert!(re(s).is_match(&store.dir(t).expect("could not get dir")));
+        }
+    }
+
+    #[test]
+    /// object_path returns a sensible string for a Table and key
+    fn test_object_path() {
+        let (store, _dir) = test_fs_store();
+        let re = |s, k| {
+            let regex_str = if MAIN_SEPARATOR == '\\' {
+                format!(
+                    r".*\.tmp.*\{}{}\{}{}\.json",
+                    MAIN_SEPARATOR, s, MAIN_SEPARATOR, k
+                )
+            } else {
+                format!(
+                    r".*\.tmp.*{}{}{}{}\.json",
+                    MAIN_SEPARATOR, s, MAIN_SEPARATOR, k
+                )
+            };
+            Regex::new(&regex_str).expect("failed to build regex")
+        };
+        for (s, t) in vec![("entries", Table::Entries), ("metas", Table::Metas)] {
+            for k in vec!["foo", "bar"] {
+                assert!(
+                    re(s, k).is_match(
+                        &store
+                            .object_path(t, &Address::from(k.to_string()))
+                            .expect("could not get object path"),
+                    )
+                );
+            }
+        }
+    }
+
+    /// content round trips through put/get/list for any store
+    fn assert_round_trip<S: ObjectStore>(store: &S) {
+        let key = Address::from("foo".to_string());
+        assert_eq!(None, store.get(Table::Entries, &key).unwrap());
+        store.put(Table::Entries, &key, "bar").unwrap();
+        assert_eq!(
+            Some("bar".to_string()),
+            store.get(Table::Entries, &key).unwrap(),
+        );
+        assert_eq!(vec!["bar".to_string()], store.list(Table::Entries).unwrap());
+    }
+
+    #[test]
+    fn test_filesystem_round_trip() {
+        let (store, _dir) = test_fs_store();
+        assert_round_trip(&store);
+    }
+
+    #[test]
+    fn test_in_memory_round_trip() {
+        assert_round_trip(&InMemoryStore::new());
+    }
+}
@@ -0,0 +1,33 @@
+use hash_table::HashTable;
+use holochain_core_types::{
+    cas::content::AddressableContent,
+    entry::test_entry,
+    entry_meta::{test_meta_a, test_meta_b},
+};
+
+/// the suite of behaviour every HashTable backend must satisfy, run against a
+/// freshly constructed table so filesystem/in-memory/sqlite backends are held
+/// to identical semantics
+pub fn standard_suite<HT: HashTable>(table: &mut HT) {
+    let entry = test_entry();
+
+    // entries round trip through put/get
+    assert_eq!(None, table.entry(&entry.address()).unwrap());
+    table.put_entry(&entry).unwrap();
+    assert_eq!(Some(entry.clone()), table.entry(&entry.address()).unwrap());
+
+    // metas round trip through assert/get and are retrievable by entry
+    let meta_a = test_meta_a();
+    let meta_b = test_meta_b();
+    table.assert_meta(&meta_a).unwrap();
+    table.assert_meta(&meta_b).unwrap();
+
+    assert_eq!(
+        Some(meta_a.clone()),
+        table.get_meta(&meta_a.address()).unwrap(),
+    );
+
+    let mut expected = vec![meta_a, meta_b];
+    expected.sort();
+    assert_eq!(expected, table.metas_from_entry(&entry).unwrap());
+}
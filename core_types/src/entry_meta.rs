@@ -2,8 +2,10 @@ use cas::content::{Address, AddressableContent, Content};
 use entry::{test_entry, Entry};
 use error::HolochainError;
 use json::{FromJson, RoundTripJson, ToJson};
-use keys::test_keys;
-use multihash::Hash;
+use json_canonical::CanonicalJson;
+use keys::{test_keys, Keys};
+use hash_spec::{HashSpec, DEFAULT_HASH_SPEC};
+use logical_clock::LogicalClock;
 use serde_json;
 use std::cmp::Ordering;
 
@@ -21,26 +23,34 @@ pub struct EntryMeta {
     entry_address: Address,
     attribute: String,
     value: String,
-    // @TODO implement local transaction ordering
+    // a unique (local to the source) monotonically increasing number drawn from
+    // the source's LogicalClock, used for crdt/ordering. zero means unstamped
     // @see https://github.com/holochain/holochain-rust/issues/138
-    // txn: String,
+    txn: u64,
     source: String,
-    // @TODO implement meta data signing
+    // detached signature, base64 encoded, of the canonical digest of
+    // (entry_address, attribute, value, source) produced by the source agent.
+    // empty for unsigned (locally constructed) metas
     // @see https://github.com/holochain/holochain-rust/issues/139
-    // signature: String,
+    signature: String,
 }
 
 impl Ord for EntryMeta {
     fn cmp(&self, other: &EntryMeta) -> Ordering {
-        // we want to sort by entry hash, then attribute name, then attribute value
+        // we want to sort by entry hash, then attribute name. when both tie, a
+        // later assertion wins: compare by (txn, source) so that conflicting
+        // values of the same attribute resolve deterministically (last writer
+        // wins), falling back to the raw value only when even that ties
         match self.entry_address.cmp(&other.entry_address) {
             Ordering::Equal => match self.attribute.cmp(&other.attribute) {
-                Ordering::Equal => self.value.cmp(&other.value),
-                Ordering::Greater => Ordering::Greater,
-                Ordering::Less => Ordering::Less,
+                Ordering::Equal => (self.txn, &self.source, &self.value).cmp(&(
+                    other.txn,
+                    &other.source,
+                    &other.value,
+                )),
+                other => other,
             },
-            Ordering::Greater => Ordering::Greater,
-            Ordering::Less => Ordering::Less,
+            other => other,
         }
     }
 }
@@ -60,10 +70,92 @@ impl EntryMeta {
             entry_address: address.clone(),
             attribute: attribute.into(),
             value: value.into(),
+            txn: 0,
             source: node_id.to_string(),
+            signature: String::new(),
         }
     }
 
+    /// Builds a new Meta stamped with the next txn from the source's clock.
+    /// Use this on local assertion so that causal ordering is preserved.
+    pub fn new_stamped(
+        clock: &mut LogicalClock,
+        node_id: &str,
+        address: &Address,
+        attribute: &str,
+        value: &str,
+    ) -> EntryMeta {
+        let mut meta = EntryMeta::new(node_id, address, attribute, value);
+        meta.txn = clock.tick();
+        meta
+    }
+
+    /// getter for txn
+    pub fn txn(&self) -> u64 {
+        self.txn
+    }
+
+    /// ingest a meta observed from another source: advance the given local
+    /// clock past this meta's txn (Lamport merge) and re-stamp it with the
+    /// resulting local value so later local comparisons are causally correct
+    pub fn ingest(&mut self, clock: &mut LogicalClock) {
+        self.txn = clock.merge(self.txn);
+    }
+
+    /// Builds a new Meta signed by the asserting agent's secret key.
+    /// The detached signature is taken over the canonical digest of
+    /// (entry_address, attribute, value, source) so that it can be verified
+    /// later against the source's public key.
+    /// @see https://github.com/holochain/holochain-rust/issues/139
+    pub fn new_signed(
+        keys: &Keys,
+        address: &Address,
+        attribute: &str,
+        value: &str,
+    ) -> Result<EntryMeta, HolochainError> {
+        let source = keys.node_id();
+        let signature = keys.sign(&EntryMeta::signing_digest(address, attribute, value, &source)?)?;
+        Ok(EntryMeta {
+            entry_address: address.clone(),
+            attribute: attribute.into(),
+            value: value.into(),
+            txn: 0,
+            source,
+            signature,
+        })
+    }
+
+    /// the stable bytes that get signed/verified for a meta assertion
+    fn signing_digest(
+        address: &Address,
+        attribute: &str,
+        value: &str,
+        source: &str,
+    ) -> Result<String, HolochainError> {
+        let tuple = (address.to_string(), attribute, value, source);
+        tuple.to_canonical_json()
+    }
+
+    /// getter for signature clone
+    pub fn signature(&self) -> String {
+        self.signature.clone()
+    }
+
+    /// verifies the detached signature against the source agent's public key
+    /// returns Ok(false) for an unsigned meta or a signature that doesn't check out
+    pub fn verify(&self) -> Result<bool, HolochainError> {
+        if self.signature.is_empty() {
+            return Ok(false);
+        }
+        let digest = EntryMeta::signing_digest(
+            &self.entry_address,
+            &self.attribute,
+            &self.value,
+            &self.source,
+        )?;
+        Keys::verify(&self.source, &self.signature, &digest)
+    }
+
     /// getter for entry
     pub fn entry_address(&self) -> &Address {
         &self.entry_address
@@ -84,19 +176,56 @@ impl EntryMeta {
         self.source.clone()
     }
 
-    pub fn make_address(address: &Address, attribute: &str) -> Address {
-        let pieces: [String; 2] = [address.clone().to_string(), attribute.to_string()];
-        let string_to_address = pieces.concat();
+    /// derive a meta address under the default hash spec.
+    ///
+    /// the address keys the assertion by (entry, attribute, value, source) so
+    /// that genuinely conflicting values/sources for the same attribute get
+    /// distinct storage keys and coexist rather than clobbering each other on
+    /// write — letting `latest_meta_for` arbitrate them (last-writer-wins).
+    /// `txn` is deliberately excluded: it's a node-local, mutable logical-clock
+    /// value, so hashing it would make the same assertion address differently
+    /// on different nodes and defeat deterministic cross-node addressing
+    pub fn make_address(
+        entry_address: &Address,
+        attribute: &str,
+        value: &str,
+        source: &str,
+    ) -> Address {
+        EntryMeta::make_address_with_spec(
+            entry_address,
+            attribute,
+            value,
+            source,
+            &DEFAULT_HASH_SPEC,
+        )
+    }
 
-        // @TODO the hashing algo should not be hardcoded
-        // @see https://github.com/holochain/holochain-rust/issues/104
-        Address::encode_from_str(&string_to_address, Hash::SHA2256)
+    /// derive a meta address under an explicit hash spec: a multihash rendered
+    /// by the spec's encoding, self-describing for multibase encodings and bare
+    /// base58 for the legacy encoding
+    /// @see https://github.com/holochain/holochain-rust/issues/104
+    pub fn make_address_with_spec(
+        entry_address: &Address,
+        attribute: &str,
+        value: &str,
+        source: &str,
+        spec: &HashSpec,
+    ) -> Address {
+        // hash the canonical digest of the assertion identity
+        let digest = (entry_address.to_string(), attribute, value, source)
+            .to_canonical_json()
+            .expect("could not canonicalize meta address pieces");
+        Address::from(spec.encode_str(&digest))
     }
 }
 
 impl ToJson for EntryMeta {
+    /// metas serialize through the canonical codec so that `content()` is
+    /// byte-for-byte deterministic across nodes; the address itself is hashed
+    /// from the canonical (entry, attribute, value, source) tuple in make_address
+    /// @see https://github.com/holochain/holochain-rust/issues/75
     fn to_json(&self) -> Result<String, HolochainError> {
-        Ok(serde_json::to_string(&self)?)
+        self.to_canonical_json()
     }
 }
 
@@ -112,7 +241,12 @@ impl RoundTripJson for EntryMeta {}
 
 impl AddressableContent for EntryMeta {
     fn address(&self) -> Address {
-        EntryMeta::make_address(&self.entry_address, &self.attribute)
+        EntryMeta::make_address(
+            &self.entry_address,
+            &self.attribute,
+            &self.value,
+            &self.source,
+        )
     }
 
     fn content(&self) -> Content {
@@ -281,9 +415,13 @@ pub mod tests {
 
     #[test]
     /// test the RoundTripJson implementation
+    ///
+    /// note the canonical codec sorts keys lexicographically, so fields come
+    /// out as attribute, entry_address, signature, source, txn, value regardless
+    /// of struct order
     fn test_json_round_trip() {
         let meta = test_meta();
-        let expected = "{\"entry_address\":\"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT\",\"attribute\":\"meta-attribute\",\"value\":\"meta value\",\"source\":\"test node id\"}";
+        let expected = "{\"attribute\":\"meta-attribute\",\"entry_address\":\"QmbXSE38SN3SuJDmHKSSw5qWWegvU7oTxrLDRavWjyxMrT\",\"signature\":\"\",\"source\":\"test node id\",\"txn\":0,\"value\":\"meta value\"}";
 
         assert_eq!(expected.to_string(), meta.to_json().unwrap());
         assert_eq!(meta, EntryMeta::from_json(&expected).unwrap());
@@ -292,4 +430,164 @@ pub mod tests {
             EntryMeta::from_json(&meta.to_json().unwrap()).unwrap()
         );
     }
+
+    #[test]
+    /// the canonical serialization is byte-for-byte stable and hence so is the
+    /// address derived from it
+    fn test_canonical_stability() {
+        let meta = test_meta();
+        assert_eq!(meta.to_json().unwrap(), meta.to_json().unwrap());
+        assert_eq!(meta.content(), meta.content());
+        assert_eq!(meta.address(), test_meta_a().address());
+    }
+
+    #[test]
+    /// a meta signed with an agent's keys verifies against that agent's public key
+    fn test_signed_meta_verifies() {
+        let meta = EntryMeta::new_signed(
+            &test_keys(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value(),
+        ).unwrap();
+        assert!(meta.verify().unwrap());
+    }
+
+    #[test]
+    /// tampering with the value invalidates the signature
+    fn test_tampered_meta_fails() {
+        let mut meta = EntryMeta::new_signed(
+            &test_keys(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value(),
+        ).unwrap();
+        meta.value = test_value_b();
+        assert!(!meta.verify().unwrap());
+    }
+
+    #[test]
+    /// claiming a different source than the signer invalidates the signature
+    fn test_wrong_source_fails() {
+        let mut meta = EntryMeta::new_signed(
+            &test_keys(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value(),
+        ).unwrap();
+        meta.source = "some other agent".to_string();
+        assert!(!meta.verify().unwrap());
+    }
+
+    #[test]
+    /// an unsigned meta does not verify
+    fn test_unsigned_meta_does_not_verify() {
+        assert!(!test_meta().verify().unwrap());
+    }
+
+    #[test]
+    /// stamping draws monotonically increasing txns from the source's clock
+    fn test_stamped_txn() {
+        let mut clock = LogicalClock::new();
+        let first = EntryMeta::new_stamped(
+            &mut clock,
+            &test_keys().node_id(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value(),
+        );
+        let second = EntryMeta::new_stamped(
+            &mut clock,
+            &test_keys().node_id(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value_b(),
+        );
+        assert_eq!(1, first.txn());
+        assert_eq!(2, second.txn());
+    }
+
+    #[test]
+    /// ingesting a remote meta merges the local clock past the incoming txn
+    fn test_ingest_merges_clock() {
+        let mut clock = LogicalClock::new();
+        let mut remote = test_meta();
+        remote.txn = 5;
+        remote.ingest(&mut clock);
+        assert_eq!(6, remote.txn());
+        assert_eq!(6, clock.current());
+    }
+
+    #[test]
+    /// a meta address under two hash specs differs and is self-describing
+    fn test_address_per_spec() {
+        use hash_spec::{Encoding, HashAlgorithm, HashSpec};
+        use multibase::Base;
+
+        let entry_address = test_entry().address();
+        let sha = EntryMeta::make_address(
+            &entry_address,
+            &test_attribute(),
+            &test_value(),
+            &test_keys().node_id(),
+        );
+        let blake = EntryMeta::make_address_with_spec(
+            &entry_address,
+            &test_attribute(),
+            &test_value(),
+            &test_keys().node_id(),
+            &HashSpec {
+                algorithm: HashAlgorithm::Blake2b256,
+                encoding: Encoding::Multibase(Base::Base58btc),
+            },
+        );
+        assert_ne!(sha, blake);
+        assert_eq!(
+            HashAlgorithm::Blake2b256,
+            HashSpec::decode(&blake.to_string()).unwrap().algorithm,
+        );
+
+        // differing value gives a distinct key so conflicts coexist
+        let other_value = EntryMeta::make_address(
+            &entry_address,
+            &test_attribute(),
+            &test_value_b(),
+            &test_keys().node_id(),
+        );
+        assert_ne!(sha, other_value);
+    }
+
+    #[test]
+    /// txn does not affect the content address — the same assertion addresses
+    /// identically regardless of node-local logical-clock state
+    fn test_txn_excluded_from_address() {
+        let mut clock = LogicalClock::new();
+        let a = EntryMeta::new_stamped(
+            &mut clock,
+            &test_keys().node_id(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value(),
+        );
+        let b = EntryMeta::new_stamped(
+            &mut clock,
+            &test_keys().node_id(),
+            &test_entry().address(),
+            &test_attribute(),
+            &test_value(),
+        );
+        assert_ne!(a.txn(), b.txn());
+        assert_eq!(a.address(), b.address());
+    }
+
+    #[test]
+    /// on a tie of (entry, attribute) the higher (txn, source) wins
+    fn test_txn_tie_break() {
+        let mut low = test_meta();
+        let mut high = test_meta();
+        low.txn = 1;
+        high.txn = 2;
+        assert!(low < high);
+        assert!(high > low);
+    }
 }
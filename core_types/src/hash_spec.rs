@@ -0,0 +1,194 @@
+use error::HolochainError;
+use multibase::{self, Base};
+use multihash::{self, Hash};
+
+/// A content-addressing hash algorithm. The algorithm is no longer hardcoded
+/// and, for multibase encodings, is recoverable straight from the address.
+/// @see https://github.com/holochain/holochain-rust/issues/104
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    Sha2_256,
+    Blake2b256,
+}
+
+impl HashAlgorithm {
+    /// the underlying multihash code for this algorithm
+    fn to_multihash(self) -> Hash {
+        match self {
+            HashAlgorithm::Sha2_256 => Hash::SHA2256,
+            HashAlgorithm::Blake2b256 => Hash::Blake2b256,
+        }
+    }
+
+    /// recover the algorithm from a decoded multihash code
+    fn from_multihash(hash: Hash) -> Result<HashAlgorithm, HolochainError> {
+        match hash {
+            Hash::SHA2256 => Ok(HashAlgorithm::Sha2_256),
+            Hash::Blake2b256 => Ok(HashAlgorithm::Blake2b256),
+            other => Err(HolochainError::ErrorGeneric(format!(
+                "unsupported multihash algorithm: {:?}",
+                other
+            ))),
+        }
+    }
+}
+
+/// How the multihash bytes are rendered into an address string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// bare base58 of the multihash, byte-identical to the legacy address
+    /// format (e.g. `Qm…`), for backward compatibility
+    Legacy,
+    /// self-describing multibase-encoded multihash (e.g. base58btc prefixes `z`)
+    Multibase(Base),
+}
+
+/// A content-addressing specification: which algorithm to hash with and how to
+/// render the resulting multihash. Threaded through address derivation so two
+/// specs over the same content yield distinct addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashSpec {
+    pub algorithm: HashAlgorithm,
+    pub encoding: Encoding,
+}
+
+/// the default spec: SHA2-256 rendered as bare base58, byte-identical to the
+/// legacy `Qm…` addresses so previously-stored content stays reachable
+pub const DEFAULT_HASH_SPEC: HashSpec = HashSpec {
+    algorithm: HashAlgorithm::Sha2_256,
+    encoding: Encoding::Legacy,
+};
+
+impl HashSpec {
+    /// derive an address string for some bytes under this spec
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let digest = multihash::encode(self.algorithm.to_multihash(), bytes)
+            .expect("multihash encoding should not fail");
+        match self.encoding {
+            // base58btc multibase minus its `z` prefix is exactly the bare
+            // base58 the legacy encoder produced
+            Encoding::Legacy => {
+                let encoded = multibase::encode(Base::Base58btc, &digest);
+                encoded[1..].to_string()
+            }
+            Encoding::Multibase(base) => multibase::encode(base, &digest),
+        }
+    }
+
+    /// convenience for hashing a string slice
+    pub fn encode_str(&self, s: &str) -> String {
+        self.encode(s.as_bytes())
+    }
+
+    /// Parse an address string, recovering the spec (algorithm + encoding) it
+    /// was produced under. A multibase-prefixed string is self-describing; a
+    /// bare base58 string is decoded as a Legacy-encoded multihash (the form the
+    /// SHA2-256 default produces), its algorithm read from the multihash. This makes
+    /// the self-describing property usable by callers, and keeps parsing
+    /// tolerant of both the legacy and the new address formats.
+    pub fn decode(address: &str) -> Result<HashSpec, HolochainError> {
+        let (spec, _digest) = HashSpec::decode_digest(address)?;
+        Ok(spec)
+    }
+
+    /// like `decode` but also returns the raw hash digest bytes
+    pub fn decode_digest(address: &str) -> Result<(HashSpec, Vec<u8>), HolochainError> {
+        // a self-describing multibase string carries a leading discriminator
+        // (e.g. `z` for base58btc); legacy `Qm…` addresses do not and fail here
+        if let Ok((base, bytes)) = multibase::decode(address) {
+            let decoded = multihash::decode(&bytes)
+                .map_err(|e| HolochainError::ErrorGeneric(e.to_string()))?;
+            return Ok((
+                HashSpec {
+                    algorithm: HashAlgorithm::from_multihash(decoded.alg)?,
+                    encoding: Encoding::Multibase(base),
+                },
+                decoded.digest.to_vec(),
+            ));
+        }
+
+        // fall back to the legacy bare base58 form by reusing multibase's
+        // base58btc decoder with its discriminator prepended
+        let (_, bytes) = multibase::decode(&format!("z{}", address))
+            .map_err(|e| HolochainError::ErrorGeneric(e.to_string()))?;
+        let decoded = multihash::decode(&bytes)
+            .map_err(|e| HolochainError::ErrorGeneric(e.to_string()))?;
+        Ok((
+            HashSpec {
+                algorithm: HashAlgorithm::from_multihash(decoded.alg)?,
+                encoding: Encoding::Legacy,
+            },
+            decoded.digest.to_vec(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the default spec emits bare base58 with no multibase prefix, matching
+    /// the legacy address format byte-for-byte
+    fn default_is_legacy_bare_base58() {
+        let digest = multihash::encode(Hash::SHA2256, b"some content").unwrap();
+        let legacy = multibase::encode(Base::Base58btc, &digest);
+        // drop the leading multibase discriminator `z`
+        assert_eq!(&legacy[1..], DEFAULT_HASH_SPEC.encode_str("some content"));
+    }
+
+    #[test]
+    /// the same content under two specs yields distinct addresses, and a
+    /// multibase spec is self-describing (the algorithm decodes from the string)
+    fn distinct_addresses_per_spec() {
+        let content = "some content";
+        let sha = DEFAULT_HASH_SPEC.encode_str(content);
+        let blake = HashSpec {
+            algorithm: HashAlgorithm::Blake2b256,
+            encoding: Encoding::Multibase(Base::Base58btc),
+        }.encode_str(content);
+
+        assert_ne!(sha, blake);
+
+        let (_, blake_bytes) = multibase::decode(&blake).unwrap();
+        assert_eq!(
+            Hash::Blake2b256.code(),
+            multihash::decode(&blake_bytes).unwrap().alg.code(),
+        );
+    }
+
+    #[test]
+    /// decode recovers the algorithm and encoding from both a legacy bare
+    /// base58 address and a self-describing multibase address
+    fn decode_recovers_spec() {
+        let content = "some content";
+
+        let legacy = DEFAULT_HASH_SPEC.encode_str(content);
+        assert_eq!(DEFAULT_HASH_SPEC, HashSpec::decode(&legacy).unwrap());
+
+        let multibase_spec = HashSpec {
+            algorithm: HashAlgorithm::Blake2b256,
+            encoding: Encoding::Multibase(Base::Base58btc),
+        };
+        let self_describing = multibase_spec.encode_str(content);
+        assert_eq!(
+            multibase_spec,
+            HashSpec::decode(&self_describing).unwrap(),
+        );
+    }
+
+    #[test]
+    /// the decoded digest matches across encodings for the same content/algorithm
+    fn decode_digest_is_encoding_independent() {
+        let content = "some content";
+        let (_, legacy_digest) =
+            HashSpec::decode_digest(&DEFAULT_HASH_SPEC.encode_str(content)).unwrap();
+        let multibase_spec = HashSpec {
+            algorithm: HashAlgorithm::Sha2_256,
+            encoding: Encoding::Multibase(Base::Base58btc),
+        };
+        let (_, multibase_digest) =
+            HashSpec::decode_digest(&multibase_spec.encode_str(content)).unwrap();
+        assert_eq!(legacy_digest, multibase_digest);
+    }
+}
@@ -0,0 +1,131 @@
+use error::HolochainError;
+use serde_json::{self, Value};
+
+/// Canonical JSON codec, in the spirit of the OLPC canonical JSON form used by
+/// TUF tooling. Two nodes that serialize the same data must produce byte-for-byte
+/// identical output so that content addresses computed from the serialization
+/// agree across the network.
+///
+/// The canonical form guarantees:
+/// - object keys sorted lexicographically by UTF-8 byte order
+/// - no insignificant whitespace
+/// - strings escaped minimally (only `"` and `\` plus the mandatory control escapes)
+/// - integers emitted without exponent or trailing decimal
+/// @see https://github.com/holochain/holochain-rust/issues/75
+pub trait CanonicalJson {
+    /// serialize self into its canonical JSON string
+    fn to_canonical_json(&self) -> Result<String, HolochainError>;
+}
+
+impl<T> CanonicalJson for T
+where
+    T: ::serde::Serialize,
+{
+    fn to_canonical_json(&self) -> Result<String, HolochainError> {
+        let value = serde_json::to_value(self)?;
+        Ok(canonicalize(&value))
+    }
+}
+
+/// recursively render a serde_json `Value` into its canonical string form
+fn canonicalize(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => if *b { "true" } else { "false" }.to_string(),
+        Value::Number(n) => {
+            // integers are emitted bare; anything that isn't an integer falls back
+            // to serde's shortest round-trippable form
+            if let Some(i) = n.as_i64() {
+                i.to_string()
+            } else if let Some(u) = n.as_u64() {
+                u.to_string()
+            } else {
+                n.to_string()
+            }
+        }
+        Value::String(s) => escape_string(s),
+        Value::Array(items) => {
+            let inner: Vec<String> = items.iter().map(canonicalize).collect();
+            format!("[{}]", inner.join(","))
+        }
+        Value::Object(map) => {
+            // sort keys by UTF-8 byte order
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+            let inner: Vec<String> = keys
+                .into_iter()
+                .map(|k| format!("{}:{}", escape_string(k), canonicalize(&map[k])))
+                .collect();
+            format!("{{{}}}", inner.join(","))
+        }
+    }
+}
+
+/// escape a string with the minimal set of escapes required by JSON
+fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0c}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Sample {
+        b: String,
+        a: u32,
+    }
+
+    #[derive(Serialize)]
+    struct SampleReordered {
+        a: u32,
+        b: String,
+    }
+
+    #[test]
+    /// keys are sorted regardless of field insertion order, byte-for-byte
+    fn stability_across_field_order() {
+        let one = Sample {
+            b: "two".into(),
+            a: 1,
+        };
+        let two = SampleReordered {
+            a: 1,
+            b: "two".into(),
+        };
+        assert_eq!(
+            one.to_canonical_json().unwrap(),
+            two.to_canonical_json().unwrap(),
+        );
+        assert_eq!("{\"a\":1,\"b\":\"two\"}", one.to_canonical_json().unwrap());
+    }
+
+    #[test]
+    /// integers carry no decimal or exponent
+    fn integers_are_bare() {
+        #[derive(Serialize)]
+        struct N {
+            n: i64,
+        }
+        assert_eq!(
+            "{\"n\":-42}",
+            N { n: -42 }.to_canonical_json().unwrap(),
+        );
+    }
+}